@@ -0,0 +1,441 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::Notify;
+
+/// Prefix the sidecar prints on the stdout line announcing its listening
+/// port once it's ready to accept requests, e.g. `SERVER_READY port=51234`.
+const READY_MARKER: &str = "SERVER_READY port=";
+/// Default timeout for `wait_for_server` if the caller doesn't override it.
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Maximum number of consecutive restart attempts before giving up.
+const MAX_RETRIES: u32 = 5;
+/// Base delay for the exponential backoff between restarts.
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound on the backoff delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(4);
+/// How many log lines to retain for late-subscribing windows.
+const LOG_BUFFER_CAPACITY: usize = 1000;
+
+/// Severity of a forwarded sidecar log line.
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Info,
+    Warn,
+}
+
+/// A single line of sidecar output, forwarded to the frontend as a
+/// `sidecar-log` event so a log-console component can render it live.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SidecarLogEvent {
+    pub level: LogLevel,
+    pub line: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl SidecarLogEvent {
+    fn new(level: LogLevel, line: String) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self { level, line, timestamp }
+    }
+}
+
+/// The sidecar's reported listening port and readiness, parsed from its
+/// `SERVER_READY` stdout line.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize)]
+pub struct ServerInfo {
+    pub port: Option<u16>,
+    pub ready: bool,
+}
+
+/// Lifecycle state of the supervised sidecar process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SidecarStatus {
+    Running,
+    Restarting,
+    Dead,
+}
+
+/// `child`, `status`, and `generation` change together and must be read or
+/// written as one unit — see `adopt_child`/`clear_child_if_current` — so
+/// they live behind a single lock rather than one mutex each.
+struct Supervised {
+    child: Option<CommandChild>,
+    status: SidecarStatus,
+    /// Bumped every time a new supervising loop is started or supervision is
+    /// stopped, so a loop that's been superseded (e.g. by `restart_server`)
+    /// can tell it's stale and exit instead of adopting a child or
+    /// respawning a competing sidecar of its own.
+    generation: u64,
+}
+
+/// Supervises the `transcribealpha-server` sidecar: spawns it, watches for
+/// unexpected termination, and restarts it with exponential backoff.
+pub struct SidecarLifecycle {
+    supervised: Mutex<Supervised>,
+    logs: Mutex<VecDeque<SidecarLogEvent>>,
+    server_info: Mutex<ServerInfo>,
+    ready_notify: Notify,
+}
+
+impl SidecarLifecycle {
+    fn new() -> Self {
+        Self {
+            supervised: Mutex::new(Supervised {
+                child: None,
+                status: SidecarStatus::Dead,
+                generation: 0,
+            }),
+            logs: Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)),
+            server_info: Mutex::new(ServerInfo::default()),
+            ready_notify: Notify::new(),
+        }
+    }
+
+    /// Starts a new generation, invalidating any supervising loop still
+    /// running under an older one.
+    fn next_generation(&self) -> u64 {
+        let mut supervised = self.supervised.lock().unwrap();
+        supervised.generation += 1;
+        supervised.generation
+    }
+
+    fn generation(&self) -> u64 {
+        self.supervised.lock().unwrap().generation
+    }
+
+    fn reset_server_info(&self) {
+        *self.server_info.lock().unwrap() = ServerInfo::default();
+    }
+
+    fn mark_ready(&self, port: u16) {
+        *self.server_info.lock().unwrap() = ServerInfo { port: Some(port), ready: true };
+        self.ready_notify.notify_waiters();
+    }
+
+    pub fn server_info(&self) -> ServerInfo {
+        *self.server_info.lock().unwrap()
+    }
+
+    fn push_log(&self, event: SidecarLogEvent) {
+        let mut logs = self.logs.lock().unwrap();
+        if logs.len() == LOG_BUFFER_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(event);
+    }
+
+    pub fn logs(&self) -> Vec<SidecarLogEvent> {
+        self.logs.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn set_status(&self, status: SidecarStatus) {
+        self.supervised.lock().unwrap().status = status;
+    }
+
+    pub fn status(&self) -> SidecarStatus {
+        self.supervised.lock().unwrap().status
+    }
+
+    /// Adopts `child` as the currently-supervised process, but only if
+    /// `generation` is still current — otherwise a newer `restart_server`/
+    /// `stop_server` call raced ahead of us while `spawn_once` was in
+    /// flight. Returns the child back to the caller (who must kill it) when
+    /// the adoption is rejected, so an orphaned process is never left
+    /// untracked and unkillable.
+    fn adopt_child(&self, generation: u64, child: CommandChild) -> Result<(), CommandChild> {
+        let mut supervised = self.supervised.lock().unwrap();
+        if supervised.generation != generation {
+            return Err(child);
+        }
+        supervised.child = Some(child);
+        supervised.status = SidecarStatus::Running;
+        Ok(())
+    }
+
+    /// Clears the tracked child, but only if `generation` is still the one
+    /// that owns it — a stale loop must not clobber a newer generation's
+    /// child. Returns whether this generation was still current.
+    fn clear_child_if_current(&self, generation: u64) -> bool {
+        let mut supervised = self.supervised.lock().unwrap();
+        if supervised.generation != generation {
+            return false;
+        }
+        supervised.child = None;
+        true
+    }
+
+    fn kill_child(&self) {
+        if let Some(child) = self.supervised.lock().unwrap().child.take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Spawns a fresh `transcribealpha-server` process and returns its handle
+/// along with the event receiver for its stdout/stderr/lifecycle events.
+fn spawn_once(
+    app: &AppHandle,
+) -> Result<(CommandChild, tauri::async_runtime::Receiver<CommandEvent>), String> {
+    let sidecar = app
+        .shell()
+        .sidecar("transcribealpha-server")
+        .map_err(|e| format!("failed to create sidecar command: {e}"))?;
+
+    sidecar
+        .spawn()
+        .map(|(rx, child)| (child, rx))
+        .map_err(|e| format!("failed to spawn sidecar: {e}"))
+}
+
+/// Exponential backoff delay for the given 1-indexed restart attempt,
+/// capped at `MAX_BACKOFF`.
+fn backoff_for_attempt(attempt: u32) -> Duration {
+    BASE_BACKOFF.saturating_mul(1 << (attempt - 1)).min(MAX_BACKOFF)
+}
+
+/// Parses a sidecar stdout line for the `SERVER_READY port=<n>` handshake,
+/// returning the port if the line matches.
+fn parse_ready_line(line: &str) -> Option<u16> {
+    line.strip_prefix(READY_MARKER)?.trim().parse().ok()
+}
+
+/// Spawns the sidecar and supervises it for the lifetime of its generation,
+/// restarting it with exponential backoff if it crashes. Starts a new
+/// generation up front, so a previously-running supervising loop (if any)
+/// will notice it's been superseded and exit instead of racing to respawn
+/// its own competing sidecar.
+pub fn spawn_supervised(app: AppHandle) {
+    let my_generation = app.state::<SidecarLifecycle>().next_generation();
+
+    tauri::async_runtime::spawn(async move {
+        let mut attempt: u32 = 0;
+
+        loop {
+            let state = app.state::<SidecarLifecycle>();
+            if state.generation() != my_generation {
+                // Superseded by a newer restart/stop before we even spawned.
+                return;
+            }
+
+            let (child, mut rx) = match spawn_once(&app) {
+                Ok(pair) => pair,
+                Err(err) => {
+                    log::error!("[sidecar] {err}");
+                    state.set_status(SidecarStatus::Dead);
+                    return;
+                }
+            };
+
+            if let Err(child) = state.adopt_child(my_generation, child) {
+                // `stop_server`/`restart_server` raced ahead of us while the
+                // process was still spawning. Kill it rather than leaving
+                // it untracked, and let the newer generation own things.
+                let _ = child.kill();
+                return;
+            }
+            state.reset_server_info();
+
+            let mut crashed = false;
+            while let Some(event) = rx.recv().await {
+                match event {
+                    CommandEvent::Stdout(line) => {
+                        let line = String::from_utf8_lossy(&line).trim().to_string();
+                        log::info!("[sidecar] {line}");
+
+                        if let Some(port) = parse_ready_line(&line) {
+                            // Reaching readiness ends a run of consecutive
+                            // crashes, so the backoff starts over.
+                            attempt = 0;
+                            state.mark_ready(port);
+                            let _ = app.emit("sidecar-ready", port);
+                        }
+
+                        let event = SidecarLogEvent::new(LogLevel::Info, line);
+                        state.push_log(event.clone());
+                        let _ = app.emit("sidecar-log", event);
+                    }
+                    CommandEvent::Stderr(line) => {
+                        let line = String::from_utf8_lossy(&line).trim().to_string();
+                        log::warn!("[sidecar] {line}");
+                        let event = SidecarLogEvent::new(LogLevel::Warn, line);
+                        state.push_log(event.clone());
+                        let _ = app.emit("sidecar-log", event);
+                    }
+                    CommandEvent::Terminated(status) => {
+                        log::info!("[sidecar] terminated with {:?}", status);
+                        crashed = status.code != Some(0);
+                        break;
+                    }
+                    CommandEvent::Error(err) => {
+                        log::error!("[sidecar] error: {}", err);
+                        crashed = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            state.reset_server_info();
+            if !state.clear_child_if_current(my_generation) {
+                // `restart_server`/`stop_server` already started a newer
+                // generation (or ended supervision); let it own the sidecar.
+                return;
+            }
+
+            let state = app.state::<SidecarLifecycle>();
+            if !crashed || state.status() == SidecarStatus::Dead {
+                // Either a clean exit or `stop_server` already marked us dead.
+                state.set_status(SidecarStatus::Dead);
+                return;
+            }
+
+            attempt += 1;
+            if attempt > MAX_RETRIES {
+                log::error!("[sidecar] giving up after {MAX_RETRIES} restart attempts");
+                state.set_status(SidecarStatus::Dead);
+                return;
+            }
+
+            let backoff = backoff_for_attempt(attempt);
+            log::warn!(
+                "[sidecar] restarting in {:?} (attempt {attempt}/{MAX_RETRIES})",
+                backoff
+            );
+            state.set_status(SidecarStatus::Restarting);
+            tokio::time::sleep(backoff).await;
+        }
+    });
+}
+
+/// Registers the lifecycle state and performs the initial spawn. Call once
+/// from `setup`.
+pub fn init(app: &AppHandle) {
+    app.manage(SidecarLifecycle::new());
+    spawn_supervised(app.clone());
+}
+
+/// Kills the current sidecar and starts a fresh supervising generation for
+/// it, e.g. after a configuration change. The old generation's loop detects
+/// it's been superseded and exits rather than also trying to respawn.
+#[tauri::command]
+pub fn restart_server(app: AppHandle, state: State<SidecarLifecycle>) {
+    state.kill_child();
+    state.set_status(SidecarStatus::Restarting);
+    spawn_supervised(app);
+}
+
+#[tauri::command]
+pub fn stop_server(state: State<SidecarLifecycle>) {
+    state.next_generation();
+    state.set_status(SidecarStatus::Dead);
+    state.kill_child();
+}
+
+#[tauri::command]
+pub fn start_server(app: AppHandle, state: State<SidecarLifecycle>) {
+    if state.status() != SidecarStatus::Dead {
+        return;
+    }
+    spawn_supervised(app);
+}
+
+#[tauri::command]
+pub fn server_status(state: State<SidecarLifecycle>) -> SidecarStatus {
+    state.status()
+}
+
+/// Returns the buffered sidecar log lines so a newly-opened window can
+/// backfill history it missed before subscribing to `sidecar-log` events.
+#[tauri::command]
+pub fn get_sidecar_logs(state: State<SidecarLifecycle>) -> Vec<SidecarLogEvent> {
+    state.logs()
+}
+
+#[tauri::command]
+pub fn get_server_info(state: State<SidecarLifecycle>) -> ServerInfo {
+    state.server_info()
+}
+
+/// Blocks until the sidecar has reported its listening port via the
+/// `SERVER_READY` handshake, or returns an error if `timeout_ms` (default
+/// 15s) elapses first. Lets the UI hold its loading screen until the
+/// backend is actually reachable instead of guessing a fixed delay.
+#[tauri::command]
+pub async fn wait_for_server(
+    state: State<'_, SidecarLifecycle>,
+    timeout_ms: Option<u64>,
+) -> Result<ServerInfo, String> {
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_READY_TIMEOUT);
+
+    let info = state.server_info();
+    if info.ready {
+        return Ok(info);
+    }
+
+    // Register for the notification before re-checking, to close the race
+    // with a readiness update that lands between the two reads.
+    let notified = state.ready_notify.notified();
+    let info = state.server_info();
+    if info.ready {
+        return Ok(info);
+    }
+
+    tokio::time::timeout(timeout, notified)
+        .await
+        .map_err(|_| "timed out waiting for sidecar to report readiness".to_string())?;
+
+    Ok(state.server_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_then_caps_at_max() {
+        assert_eq!(backoff_for_attempt(1), Duration::from_secs(1));
+        assert_eq!(backoff_for_attempt(2), Duration::from_secs(2));
+        assert_eq!(backoff_for_attempt(3), Duration::from_secs(4));
+        // Would be 8s uncapped; MAX_BACKOFF clamps it to 4s.
+        assert_eq!(backoff_for_attempt(4), MAX_BACKOFF);
+        assert_eq!(backoff_for_attempt(10), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn parses_ready_line_port() {
+        assert_eq!(parse_ready_line("SERVER_READY port=51234"), Some(51234));
+        assert_eq!(parse_ready_line("SERVER_READY port= 8080"), Some(8080));
+    }
+
+    #[test]
+    fn ignores_unrelated_lines() {
+        assert_eq!(parse_ready_line("starting up..."), None);
+        assert_eq!(parse_ready_line("SERVER_READY port=not-a-number"), None);
+    }
+
+    #[test]
+    fn log_ring_buffer_evicts_oldest() {
+        let lifecycle = SidecarLifecycle::new();
+        for i in 0..LOG_BUFFER_CAPACITY + 10 {
+            lifecycle.push_log(SidecarLogEvent::new(LogLevel::Info, i.to_string()));
+        }
+        let logs = lifecycle.logs();
+        assert_eq!(logs.len(), LOG_BUFFER_CAPACITY);
+        assert_eq!(logs.first().unwrap().line, "10");
+        assert_eq!(logs.last().unwrap().line, (LOG_BUFFER_CAPACITY + 9).to_string());
+    }
+}
@@ -0,0 +1,61 @@
+use tauri::AppHandle;
+use tauri_plugin_fs::FsExt;
+use tauri_plugin_store::StoreExt;
+
+/// Store file the workspace allowlist is persisted under.
+const STORE_FILE: &str = "workspace-scopes.json";
+/// Key within the store holding the array of allowed directory paths.
+const SCOPES_KEY: &str = "scopes";
+
+fn load_scopes(app: &AppHandle) -> Result<Vec<String>, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let scopes = store
+        .get(SCOPES_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+    Ok(scopes)
+}
+
+fn save_scopes(app: &AppHandle, scopes: &[String]) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(SCOPES_KEY, serde_json::json!(scopes));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Re-applies every previously-saved workspace directory to the fs scope.
+/// Call once from `setup` instead of granting access to `/`.
+pub fn restore_scopes(app: &AppHandle) -> Result<(), String> {
+    let scopes = load_scopes(app)?;
+    let fs_scope = app.fs_scope();
+    for path in &scopes {
+        fs_scope.allow_directory(path, true);
+    }
+    Ok(())
+}
+
+/// Grants the fs scope access to `path` and persists it to the store so it
+/// survives restarts. Call after the user picks a folder via the dialog
+/// plugin.
+#[tauri::command]
+pub fn add_workspace_scope(app: AppHandle, path: String) -> Result<(), String> {
+    let mut scopes = load_scopes(&app)?;
+    if !scopes.contains(&path) {
+        app.fs_scope().allow_directory(&path, true);
+        scopes.push(path);
+        save_scopes(&app, &scopes)?;
+    }
+    Ok(())
+}
+
+/// Revokes every saved workspace directory and clears the persisted
+/// allowlist. Access is withdrawn by forbidding each saved directory in the
+/// fs scope; the existing allow entries themselves are not removed.
+#[tauri::command]
+pub fn clear_workspace_scopes(app: AppHandle) -> Result<(), String> {
+    let scopes = load_scopes(&app)?;
+    let fs_scope = app.fs_scope();
+    for path in &scopes {
+        fs_scope.forbid_directory(path, true);
+    }
+    save_scopes(&app, &[])
+}